@@ -0,0 +1,89 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+/// Files at or below this size are hashed in full; anything larger is
+/// fingerprinted from a handful of samples instead (see `compute_cas_id`).
+const FULL_HASH_THRESHOLD: u64 = 0x100000; // 1 MiB
+const SAMPLE_COUNT: u64 = 4;
+const SAMPLE_SIZE: usize = 10 * 1024; // ~10KB per sample
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub cas_id: String,
+    pub size: u64,
+    pub paths: Vec<String>,
+}
+
+/// Computes a content-addressed id for `path`.
+///
+/// This is a *probabilistic* fingerprint, not a cryptographic guarantee of
+/// content equality: files at or below `FULL_HASH_THRESHOLD` are BLAKE3-hashed in
+/// full, but larger files are fingerprinted from `SAMPLE_COUNT` fixed-size
+/// samples spread evenly across the file rather than their entire content.
+/// Two different files can in principle share a `cas_id` if their sizes
+/// match and every sampled region happens to be identical — pass
+/// `full_hash: true` when that risk isn't acceptable (e.g. before deleting a
+/// "duplicate").
+pub(crate) fn fingerprint(path: &PathBuf, full_hash: bool) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let mut hasher = blake3::Hasher::new();
+
+    if full_hash || size <= FULL_HASH_THRESHOLD {
+        std::io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+    } else {
+        hasher.update(&size.to_le_bytes());
+
+        let mut buf = vec![0u8; SAMPLE_SIZE];
+        for i in 0..SAMPLE_COUNT {
+            let offset = (size.saturating_sub(SAMPLE_SIZE as u64) / SAMPLE_COUNT.max(1)) * i;
+            file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+            let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+            hasher.update(&buf[..n]);
+        }
+    }
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+#[tauri::command]
+pub async fn compute_cas_id(path: PathBuf, full_hash: bool) -> Result<String, String> {
+    fingerprint(&path, full_hash)
+}
+
+/// Groups `paths` by (size, sampled `cas_id`), returning only groups with
+/// more than one member. Set `full_hash` to hash every file in full instead
+/// of sampling when certainty matters more than speed.
+#[tauri::command]
+pub async fn find_duplicates(
+    paths: Vec<PathBuf>,
+    full_hash: bool,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let mut groups: HashMap<(u64, String), Vec<String>> = HashMap::new();
+
+    for path in paths {
+        let size = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => continue,
+        };
+        let Ok(cas_id) = fingerprint(&path, full_hash) else {
+            continue;
+        };
+
+        groups
+            .entry((size, cas_id))
+            .or_default()
+            .push(path.to_string_lossy().to_string());
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, cas_id), paths)| DuplicateGroup { cas_id, size, paths })
+        .collect())
+}
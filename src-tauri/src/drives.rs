@@ -0,0 +1,166 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::{DiskKind, Disks};
+use tauri::{AppHandle, Emitter};
+
+/// How often the hotplug monitor re-reads the mounted-disk list to diff
+/// against what it last saw. `sysinfo` has no native "device arrived"
+/// callback we can hook into portably, so polling is the baseline; platforms
+/// that expose one can layer it on top without changing this interval.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveInfo {
+    name: String,
+    path: String,
+    drive_type: DriveType,
+    total_space: u64,
+    available_space: u64,
+    is_removable: bool,
+    file_system: Option<String>,
+    volume_name: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum DriveType {
+    Fixed,
+    Removable,
+    Network,
+    CdRom,
+    Unknown,
+}
+
+impl From<(DiskKind, bool)> for DriveType {
+    fn from((kind, is_removable): (DiskKind, bool)) -> Self {
+        if is_removable {
+            DriveType::Removable
+        } else {
+            match kind {
+                DiskKind::HDD | DiskKind::SSD => DriveType::Fixed,
+                DiskKind::Unknown(_) => DriveType::Unknown,
+            }
+        }
+    }
+}
+
+fn snapshot() -> Vec<DriveInfo> {
+    Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| {
+            let path = disk.mount_point().to_string_lossy().into_owned();
+            // Extract drive letter with colon for Windows (e.g., "C:\\" -> "C:")
+            let name = if path.len() >= 2 && path.chars().nth(1) == Some(':') {
+                path.chars().take(2).collect::<String>()
+            } else {
+                disk.name().to_string_lossy().into_owned()
+            };
+
+            DriveInfo {
+                name,
+                path,
+                drive_type: (disk.kind(), disk.is_removable()).into(),
+                total_space: disk.total_space(),
+                available_space: disk.available_space(),
+                is_removable: disk.is_removable(),
+                file_system: Some(disk.file_system().to_string_lossy().into_owned()),
+                volume_name: {
+                    let disk_name = disk.name().to_string_lossy();
+                    if disk_name.is_empty() {
+                        None
+                    } else {
+                        Some(disk_name.into_owned())
+                    }
+                },
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn list_drives() -> Result<Vec<DriveInfo>, String> {
+    Ok(snapshot())
+}
+
+/// Owns the background task that watches for drives being attached or
+/// detached and emits `drive://attached` / `drive://detached` to the window.
+pub struct DriveMonitor {
+    // Each running poll thread gets its own flag rather than sharing one
+    // across restarts. A bare shared flag would let a `stop()` immediately
+    // followed by `start()` race: the old thread could still be mid-`sleep`,
+    // wake up, see the flag flipped back to `true` by the new `start()`, and
+    // keep polling alongside the freshly spawned thread. Swapping in a fresh
+    // `Arc` on every `start()` means a stopped thread's flag can never be
+    // revived by a later start.
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl DriveMonitor {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+
+    pub fn start(&self, app: AppHandle) {
+        let mut running = self.running.lock().unwrap();
+        if running.is_some() {
+            return; // already running
+        }
+
+        let flag = Arc::new(AtomicBool::new(true));
+        *running = Some(flag.clone());
+        drop(running);
+
+        std::thread::spawn(move || {
+            let mut known: HashMap<String, DriveInfo> =
+                snapshot().into_iter().map(|d| (d.path.clone(), d)).collect();
+
+            while flag.load(Ordering::SeqCst) {
+                std::thread::sleep(POLL_INTERVAL);
+
+                let current: HashMap<String, DriveInfo> =
+                    snapshot().into_iter().map(|d| (d.path.clone(), d)).collect();
+
+                for (path, drive) in &current {
+                    if !known.contains_key(path) {
+                        let _ = app.emit("drive://attached", drive.clone());
+                    }
+                }
+
+                for (path, drive) in known.iter() {
+                    if !current.contains_key(path) {
+                        let _ = app.emit("drive://detached", drive.clone());
+                    }
+                }
+
+                known = current;
+            }
+        });
+    }
+
+    pub fn stop(&self) {
+        if let Some(flag) = self.running.lock().unwrap().take() {
+            flag.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn start_drive_monitor(
+    app: AppHandle,
+    state: tauri::State<'_, DriveMonitor>,
+) -> Result<(), String> {
+    state.start(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_drive_monitor(state: tauri::State<'_, DriveMonitor>) -> Result<(), String> {
+    state.stop();
+    Ok(())
+}
@@ -0,0 +1,529 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::ipc::Channel;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::absolute_clean_path;
+use crate::thumbnails::{generate_and_cache, ThumbnailCache};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    /// Unused today — `spawn` starts the worker thread immediately, so a job
+    /// goes straight to `Running`. Reserved for if job execution ever gets a
+    /// concurrency cap and needs an actual wait state.
+    Queued,
+    Running,
+    Paused,
+    /// Cooperatively stopped via `cancel_job`, as opposed to `Failed`, which
+    /// is reserved for the job itself erroring out.
+    Cancelled,
+    Completed,
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum JobEvent {
+    #[serde(rename_all = "camelCase")]
+    Progress {
+        job_id: String,
+        status: JobStatus,
+        processed: u64,
+    },
+    /// A matched path found by a `SearchJob`. Other job kinds only ever
+    /// emit `Progress`/`Finished`.
+    #[serde(rename_all = "camelCase")]
+    SearchResult {
+        job_id: String,
+        path: String,
+        name: String,
+        is_file: bool,
+        size: u64,
+        modified: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Finished { job_id: String, status: JobStatus },
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSummary {
+    pub job_id: String,
+    pub kind: &'static str,
+    pub status: JobStatus,
+}
+
+/// The arguments a job was started with, persisted next to its checkpoint so
+/// a `JobManager` created in a fresh process can reconstruct the job before
+/// resuming it.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+enum JobSpec {
+    Search {
+        root: PathBuf,
+        query: String,
+    },
+    Thumbnails {
+        paths: Vec<PathBuf>,
+        max_dim: u32,
+        cache_dir: PathBuf,
+    },
+}
+
+impl JobSpec {
+    fn into_job(self) -> Box<dyn Job> {
+        match self {
+            JobSpec::Search { root, query } => Box::new(SearchJob { root, query }),
+            JobSpec::Thumbnails {
+                paths,
+                max_dim,
+                cache_dir,
+            } => Box::new(ThumbnailJob {
+                paths,
+                max_dim,
+                cache: ThumbnailCache::new(cache_dir),
+            }),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            JobSpec::Search { .. } => "search",
+            JobSpec::Thumbnails { .. } => "thumbnails",
+        }
+    }
+}
+
+/// Checkpoint persisted for an in-flight `SearchJob`. `WalkDir` gives no
+/// ordering guarantee across runs and the tree can change between pause and
+/// resume, so fast-forwarding to a single "last visited" path isn't safe —
+/// instead we re-walk the whole tree on resume and skip paths already in
+/// `emitted`, an order-independent, change-tolerant cursor. `emitted` grows
+/// with the total number of matches found rather than remaining work, which
+/// is the tradeoff for making resume order-independent; unlike interactive
+/// `search::search_files`, this job has no total-result cap to bound it by.
+#[derive(Serialize, Deserialize, Default)]
+struct SearchCheckpoint {
+    emitted: HashSet<PathBuf>,
+    processed: u64,
+}
+
+/// Everything written to `<job_id>.checkpoint`: the spec needed to rebuild
+/// the job plus its kind-specific progress blob.
+#[derive(Serialize, Deserialize)]
+struct PersistedJob {
+    spec: JobSpec,
+    state: Vec<u8>,
+}
+
+/// How many entries a `Job` processes between unconditional checkpoint
+/// flushes, independent of pausing. Without this, a job that's `Running`
+/// when the app quits or crashes has no on-disk checkpoint to resume from
+/// at all, since one is otherwise only written when `pause_job` is called.
+const CHECKPOINT_INTERVAL: u64 = 200;
+
+/// Cooperative controls checked by a running `Job` between units of work.
+#[derive(Default)]
+struct JobControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+/// A unit of long-running work the `JobManager` can start, pause, resume and
+/// persist across app restarts. Implementors should check `control`
+/// periodically and bail out with a fresh checkpoint when asked to pause.
+/// They should also call `persist_checkpoint` every so often during the
+/// walk itself (not just on pause) so a job that's `Running` when the app
+/// quits or crashes still has a recent checkpoint to resume from, instead
+/// of restarting from scratch.
+trait Job: Send {
+    fn run(
+        &mut self,
+        checkpoint: Option<Vec<u8>>,
+        control: &JobControl,
+        job_id: &str,
+        on_progress: &Channel<JobEvent>,
+        persist_checkpoint: &dyn Fn(Vec<u8>),
+    ) -> Result<RunOutcome, String>;
+}
+
+/// What a `Job::run` call produced: its terminal status and, for a `Paused`
+/// job, the checkpoint blob to persist.
+struct RunOutcome {
+    status: JobStatus,
+    checkpoint: Option<Vec<u8>>,
+}
+
+struct JobRecord {
+    spec: JobSpec,
+    status: JobStatus,
+    control: Arc<JobControl>,
+}
+
+/// Owns every job the app knows about, persisting checkpoints to disk so a
+/// job that was `Running` or `Paused` when the app quit can be resumed on
+/// next launch rather than restarted from scratch.
+pub struct JobManager {
+    jobs: Arc<Mutex<HashMap<String, JobRecord>>>,
+    jobs_dir: PathBuf,
+}
+
+impl JobManager {
+    /// Creates the manager and reloads any unfinished jobs found under
+    /// `jobs_dir`, marking them `Paused` until something resumes them.
+    pub fn new(jobs_dir: PathBuf) -> Self {
+        let _ = fs::create_dir_all(&jobs_dir);
+
+        let mut jobs = HashMap::new();
+        if let Ok(entries) = fs::read_dir(&jobs_dir) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("checkpoint") {
+                    continue;
+                }
+                let Some(job_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if let Ok(bytes) = fs::read(&path) {
+                    if let Ok(persisted) = rmp_serde::from_slice::<PersistedJob>(&bytes) {
+                        jobs.insert(
+                            job_id.to_string(),
+                            JobRecord {
+                                spec: persisted.spec,
+                                status: JobStatus::Paused,
+                                control: Arc::new(JobControl::default()),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        Self {
+            jobs: Arc::new(Mutex::new(jobs)),
+            jobs_dir,
+        }
+    }
+
+    fn checkpoint_path(&self, job_id: &str) -> PathBuf {
+        self.jobs_dir.join(format!("{job_id}.checkpoint"))
+    }
+
+    fn save_checkpoint(&self, job_id: &str, spec: &JobSpec, state: Vec<u8>) {
+        if let Ok(bytes) = rmp_serde::to_vec(&PersistedJob {
+            spec: spec.clone(),
+            state,
+        }) {
+            let _ = fs::write(self.checkpoint_path(job_id), bytes);
+        }
+    }
+
+    fn load_state(&self, job_id: &str) -> Option<Vec<u8>> {
+        let bytes = fs::read(self.checkpoint_path(job_id)).ok()?;
+        rmp_serde::from_slice::<PersistedJob>(&bytes)
+            .ok()
+            .map(|p| p.state)
+    }
+
+    fn clear_checkpoint(&self, job_id: &str) {
+        let _ = fs::remove_file(self.checkpoint_path(job_id));
+    }
+
+    fn spawn(&self, job_id: String, spec: JobSpec, state: Option<Vec<u8>>, on_event: Channel<JobEvent>) {
+        let control = Arc::new(JobControl::default());
+        self.jobs.lock().unwrap().insert(
+            job_id.clone(),
+            JobRecord {
+                spec: spec.clone(),
+                status: JobStatus::Running,
+                control: control.clone(),
+            },
+        );
+
+        let jobs = self.jobs.clone();
+        let jobs_dir = self.jobs_dir.clone();
+        let mut job = spec.clone().into_job();
+
+        std::thread::spawn(move || {
+            let manager = JobManager { jobs: jobs.clone(), jobs_dir };
+            let persist_checkpoint = |blob: Vec<u8>| manager.save_checkpoint(&job_id, &spec, blob);
+            let outcome = job
+                .run(state, &control, &job_id, &on_event, &persist_checkpoint)
+                .unwrap_or(RunOutcome {
+                    status: JobStatus::Failed,
+                    checkpoint: None,
+                });
+
+            match (&outcome.status, &outcome.checkpoint) {
+                (JobStatus::Completed, _) | (JobStatus::Failed, _) | (JobStatus::Cancelled, _) => {
+                    manager.clear_checkpoint(&job_id);
+                }
+                (_, Some(blob)) => manager.save_checkpoint(&job_id, &spec, blob.clone()),
+                _ => {}
+            }
+
+            if let Some(record) = jobs.lock().unwrap().get_mut(&job_id) {
+                record.status = outcome.status;
+            }
+
+            let _ = on_event.send(JobEvent::Finished {
+                job_id,
+                status: outcome.status,
+            });
+        });
+    }
+
+    pub fn list_jobs(&self) -> Vec<JobSummary> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(job_id, record)| JobSummary {
+                job_id: job_id.clone(),
+                kind: record.spec.kind(),
+                status: record.status,
+            })
+            .collect()
+    }
+
+    pub fn pause_job(&self, job_id: &str) -> Result<(), String> {
+        let jobs = self.jobs.lock().unwrap();
+        let record = jobs.get(job_id).ok_or("job not found")?;
+        record.control.paused.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn cancel_job(&self, job_id: &str) -> Result<(), String> {
+        let jobs = self.jobs.lock().unwrap();
+        let record = jobs.get(job_id).ok_or("job not found")?;
+        record.control.cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Asks every `Running` job to pause so its checkpoint lands on disk
+    /// before the process exits, rather than losing that progress. Called
+    /// from the app's exit handler; cooperative like `pause_job`, so it only
+    /// helps if the job's worker thread gets a chance to observe it and
+    /// return before the process is actually torn down.
+    pub fn pause_all(&self) {
+        let jobs = self.jobs.lock().unwrap();
+        for record in jobs.values() {
+            if record.status == JobStatus::Running {
+                record.control.paused.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Resumes a `Paused` job, feeding it back the checkpoint saved the last
+    /// time it stopped (whether that was this process or a previous one).
+    pub fn resume_job(&self, job_id: String, on_event: Channel<JobEvent>) -> Result<(), String> {
+        let spec = {
+            let jobs = self.jobs.lock().unwrap();
+            match jobs.get(&job_id) {
+                Some(record) if record.status == JobStatus::Paused => record.spec.clone(),
+                Some(_) => return Err("job is not paused".to_string()),
+                None => return Err("job not found".to_string()),
+            }
+        };
+        let state = self.load_state(&job_id);
+        self.spawn(job_id, spec, state, on_event);
+        Ok(())
+    }
+
+    /// Starts a fresh recursive search job rooted at `root` for `query`.
+    pub fn start_search_job(&self, root: PathBuf, query: String, on_event: Channel<JobEvent>) -> String {
+        let job_id = Uuid::new_v4().to_string();
+        self.spawn(job_id.clone(), JobSpec::Search { root, query }, None, on_event);
+        job_id
+    }
+
+    /// Starts a job that thumbnails every path in `paths` into `cache_dir`,
+    /// reporting one `JobEvent::Progress` per file processed.
+    pub fn start_thumbnail_job(
+        &self,
+        paths: Vec<PathBuf>,
+        max_dim: u32,
+        cache_dir: PathBuf,
+        on_event: Channel<JobEvent>,
+    ) -> String {
+        let job_id = Uuid::new_v4().to_string();
+        self.spawn(
+            job_id.clone(),
+            JobSpec::Thumbnails {
+                paths,
+                max_dim,
+                cache_dir,
+            },
+            None,
+            on_event,
+        );
+        job_id
+    }
+}
+
+struct SearchJob {
+    root: PathBuf,
+    query: String,
+}
+
+impl Job for SearchJob {
+    fn run(
+        &mut self,
+        checkpoint: Option<Vec<u8>>,
+        control: &JobControl,
+        job_id: &str,
+        on_progress: &Channel<JobEvent>,
+        persist_checkpoint: &dyn Fn(Vec<u8>),
+    ) -> Result<RunOutcome, String> {
+        let mut resume_from: SearchCheckpoint = checkpoint
+            .and_then(|blob| rmp_serde::from_slice(&blob).ok())
+            .unwrap_or_default();
+
+        let query = self.query.to_lowercase();
+        let mut processed = resume_from.processed;
+
+        for entry in WalkDir::new(&self.root)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if control.cancelled.load(Ordering::SeqCst) {
+                return Ok(RunOutcome {
+                    status: JobStatus::Cancelled,
+                    checkpoint: None,
+                });
+            }
+
+            if control.paused.load(Ordering::SeqCst) {
+                let blob = rmp_serde::to_vec(&resume_from).map_err(|e| e.to_string())?;
+                return Ok(RunOutcome {
+                    status: JobStatus::Paused,
+                    checkpoint: Some(blob),
+                });
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.to_lowercase().contains(&query) {
+                let path = entry.path().to_path_buf();
+                if resume_from.emitted.contains(&path) {
+                    continue;
+                }
+
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+
+                processed += 1;
+                resume_from.emitted.insert(path.clone());
+                resume_from.processed = processed;
+
+                let absolute_path = absolute_clean_path(&path);
+
+                let _ = on_progress.send(JobEvent::SearchResult {
+                    job_id: job_id.to_string(),
+                    path: absolute_path,
+                    name,
+                    is_file: metadata.is_file(),
+                    size: metadata.len(),
+                    modified: metadata
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0),
+                });
+                let _ = on_progress.send(JobEvent::Progress {
+                    job_id: job_id.to_string(),
+                    status: JobStatus::Running,
+                    processed,
+                });
+
+                if processed % CHECKPOINT_INTERVAL == 0 {
+                    if let Ok(blob) = rmp_serde::to_vec(&resume_from) {
+                        persist_checkpoint(blob);
+                    }
+                }
+            }
+        }
+
+        Ok(RunOutcome {
+            status: JobStatus::Completed,
+            checkpoint: None,
+        })
+    }
+}
+
+/// Checkpoint for a `ThumbnailJob`: how many entries of `paths` (in order)
+/// have already been thumbnailed.
+#[derive(Serialize, Deserialize, Default)]
+struct ThumbnailCheckpoint {
+    processed: usize,
+}
+
+struct ThumbnailJob {
+    paths: Vec<PathBuf>,
+    max_dim: u32,
+    cache: ThumbnailCache,
+}
+
+impl Job for ThumbnailJob {
+    fn run(
+        &mut self,
+        checkpoint: Option<Vec<u8>>,
+        control: &JobControl,
+        job_id: &str,
+        on_progress: &Channel<JobEvent>,
+        persist_checkpoint: &dyn Fn(Vec<u8>),
+    ) -> Result<RunOutcome, String> {
+        let resume_from: ThumbnailCheckpoint = checkpoint
+            .and_then(|blob| rmp_serde::from_slice(&blob).ok())
+            .unwrap_or_default();
+
+        let mut processed = resume_from.processed;
+
+        while processed < self.paths.len() {
+            if control.cancelled.load(Ordering::SeqCst) {
+                return Ok(RunOutcome {
+                    status: JobStatus::Cancelled,
+                    checkpoint: None,
+                });
+            }
+
+            if control.paused.load(Ordering::SeqCst) {
+                let blob = rmp_serde::to_vec(&ThumbnailCheckpoint { processed })
+                    .map_err(|e| e.to_string())?;
+                return Ok(RunOutcome {
+                    status: JobStatus::Paused,
+                    checkpoint: Some(blob),
+                });
+            }
+
+            // Skip files that fail to thumbnail rather than aborting the batch.
+            let _ = generate_and_cache(&self.paths[processed], self.max_dim, &self.cache);
+            processed += 1;
+
+            let _ = on_progress.send(JobEvent::Progress {
+                job_id: job_id.to_string(),
+                status: JobStatus::Running,
+                processed: processed as u64,
+            });
+
+            if processed as u64 % CHECKPOINT_INTERVAL == 0 {
+                if let Ok(blob) = rmp_serde::to_vec(&ThumbnailCheckpoint { processed }) {
+                    persist_checkpoint(blob);
+                }
+            }
+        }
+
+        Ok(RunOutcome {
+            status: JobStatus::Completed,
+            checkpoint: None,
+        })
+    }
+}
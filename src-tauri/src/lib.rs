@@ -1,230 +1,37 @@
+mod cas;
+mod drives;
+mod jobs;
 mod pty;
+mod search;
+mod thumbnails;
+mod watcher;
 
+use cas::{compute_cas_id, find_duplicates};
+use drives::{list_drives, start_drive_monitor, stop_drive_monitor, DriveMonitor};
+use jobs::{JobEvent, JobManager, JobSummary};
 use pty::PtyManager;
-use serde::Serialize;
+use search::{cancel_search, search_files, SearchManager};
 use std::path::PathBuf;
-use sysinfo::{DiskKind, Disks};
 use tauri::ipc::Channel;
-use walkdir::WalkDir;
-
-// Add a constant for max results per batch
-const MAX_RESULTS_PER_BATCH: usize = 20;
-const MAX_TOTAL_RESULTS: usize = 100;
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase", tag = "event", content = "data")]
-enum SearchEvent {
-    #[serde(rename_all = "camelCase")]
-    Started { query: String, search_id: u32 },
-    #[serde(rename_all = "camelCase")]
-    Result {
-        search_id: u32,
-        path: String,
-        name: String,
-        is_file: bool,
-        size: u64,
-        modified: u64,
-    },
-    #[serde(rename_all = "camelCase")]
-    Finished {
-        search_id: u32,
-        total_matches: usize,
-        has_more: bool,
-    },
-}
+use tauri::Manager;
+use thumbnails::{generate_thumbnail, start_thumbnail_job, ThumbnailCache};
+use watcher::{FsEvent, WatchManager};
 
 // First, let's create a helper function to clean Windows paths
-fn clean_path(path: String) -> String {
+pub(crate) fn clean_path(path: String) -> String {
     path.replace("\\\\?\\", "") // Remove Windows extended path prefix
         .replace("\\", "/") // Normalize separators
 }
 
-#[tauri::command]
-async fn search_files(
-    path: PathBuf,
-    query: String,
-    search_id: u32,
-    on_event: Channel<SearchEvent>,
-) -> Result<(), String> {
-    println!("search_files called with query: {}", query);
-
-    let _ = on_event.send(SearchEvent::Started {
-        query: query.clone(),
-        search_id,
-    });
-
-    let mut total_matches = 0;
-    let mut sent_results = 0;
-    let query = query.to_lowercase();
-    let mut results = Vec::new();
-
-    // Use WalkDir for recursive search
-    for entry in WalkDir::new(&path)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.to_lowercase().contains(&query) {
-            if let Ok(metadata) = entry.metadata() {
-                total_matches += 1;
-
-                // Store all matches
-                results.push((entry.path().to_path_buf(), metadata));
-
-                // Send results in batches
-                if results.len() >= MAX_RESULTS_PER_BATCH {
-                    for (path, metadata) in results.drain(..MAX_RESULTS_PER_BATCH) {
-                        if sent_results >= MAX_TOTAL_RESULTS {
-                            break;
-                        }
-
-                        let absolute_path = clean_path(
-                            path.canonicalize()
-                                .unwrap_or(path.to_path_buf())
-                                .to_string_lossy()
-                                .to_string(),
-                        );
-
-                        let name = path
-                            .file_name()
-                            .map(|n| n.to_string_lossy().to_string())
-                            .unwrap_or_default();
-
-                        let _ = on_event.send(SearchEvent::Result {
-                            search_id,
-                            path: absolute_path,
-                            name,
-                            is_file: metadata.is_file(),
-                            size: metadata.len(),
-                            modified: metadata
-                                .modified()
-                                .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs())
-                                .unwrap_or(0),
-                        });
-                        sent_results += 1;
-                    }
-                }
-
-                if sent_results >= MAX_TOTAL_RESULTS {
-                    break;
-                }
-            }
-        }
-    }
-
-    // Send remaining results
-    for (path, metadata) in results.iter().take(MAX_RESULTS_PER_BATCH) {
-        if sent_results >= MAX_TOTAL_RESULTS {
-            break;
-        }
-
-        let absolute_path = clean_path(
-            path.canonicalize()
-                .unwrap_or(path.to_path_buf())
-                .to_string_lossy()
-                .to_string(),
-        );
-
-        let name = path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_default();
-
-        let _ = on_event.send(SearchEvent::Result {
-            search_id,
-            path: absolute_path,
-            name,
-            is_file: metadata.is_file(),
-            size: metadata.len(),
-            modified: metadata
-                .modified()
-                .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs())
-                .unwrap_or(0),
-        });
-        sent_results += 1;
-    }
-
-    // Send finished event with has_more flag
-    let _ = on_event.send(SearchEvent::Finished {
-        search_id,
-        total_matches,
-        has_more: total_matches > sent_results,
-    });
-
-    Ok(())
-}
-
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct DriveInfo {
-    name: String,
-    path: String,
-    drive_type: DriveType,
-    total_space: u64,
-    available_space: u64,
-    is_removable: bool,
-    file_system: Option<String>,
-    volume_name: Option<String>,
-}
-
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-enum DriveType {
-    Fixed,
-    Removable,
-    Network,
-    CdRom,
-    Unknown,
-}
-
-impl From<(DiskKind, bool)> for DriveType {
-    fn from((kind, is_removable): (DiskKind, bool)) -> Self {
-        if is_removable {
-            DriveType::Removable
-        } else {
-            match kind {
-                DiskKind::HDD | DiskKind::SSD => DriveType::Fixed,
-                DiskKind::Unknown(_) => DriveType::Unknown,
-            }
-        }
-    }
-}
-
-#[tauri::command]
-async fn list_drives() -> Result<Vec<DriveInfo>, String> {
-    let drives = Disks::new_with_refreshed_list();
-
-    Ok(drives
-        .iter()
-        .map(|disk| {
-            let path = disk.mount_point().to_string_lossy().into_owned();
-            // Extract drive letter with colon for Windows (e.g., "C:\\" -> "C:")
-            let name = if path.len() >= 2 && path.chars().nth(1) == Some(':') {
-                path.chars().take(2).collect::<String>() // Take first two chars ("C:")
-            } else {
-                disk.name().to_string_lossy().into_owned()
-            };
-
-            DriveInfo {
-                name,
-                path,
-                drive_type: (disk.kind(), disk.is_removable()).into(),
-                total_space: disk.total_space(),
-                available_space: disk.available_space(),
-                is_removable: disk.is_removable(),
-                file_system: Some(disk.file_system().to_string_lossy().into_owned()),
-                volume_name: {
-                    let disk_name = disk.name().to_string_lossy();
-                    if disk_name.is_empty() {
-                        None
-                    } else {
-                        Some(disk_name.into_owned())
-                    }
-                },
-            }
-        })
-        .collect())
+/// Canonicalizes `path` (falling back to it as-is if that fails, e.g. for a
+/// path that no longer exists) and cleans the result for display.
+pub(crate) fn absolute_clean_path(path: &std::path::Path) -> String {
+    clean_path(
+        path.canonicalize()
+            .unwrap_or_else(|_| path.to_path_buf())
+            .to_string_lossy()
+            .to_string(),
+    )
 }
 
 #[tauri::command]
@@ -263,12 +70,71 @@ async fn destroy_pty(state: tauri::State<'_, PtyManager>, pty_id: String) -> Res
     Ok(())
 }
 
+#[tauri::command]
+async fn start_search_job(
+    state: tauri::State<'_, JobManager>,
+    path: PathBuf,
+    query: String,
+    on_event: Channel<JobEvent>,
+) -> Result<String, String> {
+    Ok(state.start_search_job(path, query, on_event))
+}
+
+#[tauri::command]
+async fn resume_job(
+    state: tauri::State<'_, JobManager>,
+    job_id: String,
+    on_event: Channel<JobEvent>,
+) -> Result<(), String> {
+    state.resume_job(job_id, on_event)
+}
+
+#[tauri::command]
+async fn pause_job(state: tauri::State<'_, JobManager>, job_id: String) -> Result<(), String> {
+    state.pause_job(&job_id)
+}
+
+#[tauri::command]
+async fn cancel_job(state: tauri::State<'_, JobManager>, job_id: String) -> Result<(), String> {
+    state.cancel_job(&job_id)
+}
+
+#[tauri::command]
+async fn list_jobs(state: tauri::State<'_, JobManager>) -> Result<Vec<JobSummary>, String> {
+    Ok(state.list_jobs())
+}
+
+#[tauri::command]
+async fn watch_directory(
+    state: tauri::State<'_, WatchManager>,
+    path: PathBuf,
+    watch_id: String,
+    on_event: Channel<FsEvent>,
+) -> Result<(), String> {
+    state.watch_directory(watch_id, path, on_event)
+}
+
+#[tauri::command]
+async fn unwatch_directory(state: tauri::State<'_, WatchManager>, watch_id: String) -> Result<(), String> {
+    state.unwatch_directory(&watch_id)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let pty_manager = PtyManager::new();
 
     tauri::Builder::default()
         .manage(pty_manager)
+        .manage(WatchManager::new())
+        .manage(DriveMonitor::new())
+        .manage(SearchManager::new())
+        .setup(|app| {
+            let jobs_dir = app.path().app_data_dir()?.join("jobs");
+            app.manage(JobManager::new(jobs_dir));
+            app.manage(ThumbnailCache::new(app.path().app_data_dir()?.join("thumbnails")));
+            app.state::<DriveMonitor>().start(app.handle().clone());
+            Ok(())
+        })
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_os::init())
@@ -280,7 +146,29 @@ pub fn run() {
             write_pty,
             resize_pty,
             destroy_pty,
+            start_search_job,
+            resume_job,
+            pause_job,
+            cancel_job,
+            list_jobs,
+            watch_directory,
+            unwatch_directory,
+            compute_cas_id,
+            find_duplicates,
+            start_drive_monitor,
+            stop_drive_monitor,
+            generate_thumbnail,
+            start_thumbnail_job,
+            cancel_search,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Give in-flight jobs a chance to write a fresh checkpoint
+            // instead of silently losing whatever progress they made since
+            // their last one.
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                app_handle.state::<JobManager>().pause_all();
+            }
+        });
 }
@@ -1,102 +1,337 @@
-use serde::Serialize;
-use std::path::PathBuf;
-use tauri::ipc::Channel;
-use tokio::fs;
-use walkdir::WalkDir;
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase", tag = "event", content = "data")]
-enum SearchEvent {
-    #[serde(rename_all = "camelCase")]
-    Started { query: String, search_id: u32 },
-    #[serde(rename_all = "camelCase")]
-    Result {
-        search_id: u32,
-        path: String,
-        name: String,
-        is_file: bool,
-        size: u64,
-        modified: u64,
-    },
-    #[serde(rename_all = "camelCase")]
-    Finished {
-        search_id: u32,
-        total_matches: usize,
-    },
-}
-
-#[tauri::command]
-pub async fn search_files(
-    path: PathBuf,
-    query: String,
-    search_id: u32,
-    on_event: Channel<SearchEvent>,
-) -> Result<(), String> {
-    on_event
-        .send(SearchEvent::Started {
-            query: query.clone(),
-            search_id,
-        })
-        .unwrap();
-
-    let mut total_matches = 0;
-    let query = query.to_lowercase();
-
-    // First search current directory (fast results)
-    let mut entries = fs::read_dir(&path).await.unwrap();
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.to_lowercase().contains(&query) {
-            let metadata = entry.metadata().await.unwrap();
-            on_event
-                .send(SearchEvent::Result {
-                    search_id,
-                    path: entry.path().to_string_lossy().to_string(),
-                    name,
-                    is_file: metadata.is_file(),
-                    size: metadata.len(),
-                    modified: metadata
-                        .modified()
-                        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs())
-                        .unwrap_or(0),
-                })
-                .unwrap();
-            total_matches += 1;
-        }
-    }
-
-    // Then start recursive search
-    for entry in WalkDir::new(path)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.to_lowercase().contains(&query) {
-            let metadata = entry.metadata().unwrap();
-            on_event
-                .send(SearchEvent::Result {
-                    search_id,
-                    path: entry.path().to_string_lossy().to_string(),
-                    name,
-                    is_file: metadata.is_file(),
-                    size: metadata.len(),
-                    modified: metadata
-                        .modified()
-                        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs())
-                        .unwrap_or(0),
-                })
-                .unwrap();
-            total_matches += 1;
-        }
-    }
-
-    on_event
-        .send(SearchEvent::Finished {
-            search_id,
-            total_matches,
-        })
-        .unwrap();
-
-    Ok(())
-}
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::ipc::Channel;
+use walkdir::WalkDir;
+
+use crate::absolute_clean_path;
+
+const MAX_RESULTS_PER_BATCH: usize = 20;
+const MAX_TOTAL_RESULTS: usize = 100;
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOptions {
+    pub query: String,
+    #[serde(default)]
+    pub fuzzy: bool,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    #[serde(default)]
+    pub modified_after: Option<u64>,
+    #[serde(default)]
+    pub modified_before: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum SearchEvent {
+    #[serde(rename_all = "camelCase")]
+    Started { query: String, search_id: u32 },
+    #[serde(rename_all = "camelCase")]
+    Result {
+        search_id: u32,
+        path: String,
+        name: String,
+        is_file: bool,
+        size: u64,
+        modified: u64,
+        score: i64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Finished {
+        search_id: u32,
+        total_matches: usize,
+        has_more: bool,
+    },
+}
+
+struct Match {
+    path: PathBuf,
+    metadata: std::fs::Metadata,
+    score: i64,
+}
+
+/// Tracks the cancellation flag for every in-flight search so a new
+/// keystroke can abort a stale one instead of waiting for it to finish.
+pub struct SearchManager {
+    active: Arc<Mutex<HashMap<u32, Arc<AtomicBool>>>>,
+}
+
+impl SearchManager {
+    pub fn new() -> Self {
+        Self {
+            active: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn register(&self, search_id: u32) -> Arc<AtomicBool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.active
+            .lock()
+            .unwrap()
+            .insert(search_id, cancelled.clone());
+        cancelled
+    }
+
+    fn unregister(&self, search_id: u32) {
+        self.active.lock().unwrap().remove(&search_id);
+    }
+
+    pub fn cancel(&self, search_id: u32) -> Result<(), String> {
+        let active = self.active.lock().unwrap();
+        let cancelled = active.get(&search_id).ok_or("search not found")?;
+        cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Scores `name` as a fuzzy subsequence match for `query`, rewarding
+/// contiguous runs and matches that start at the beginning of the name or
+/// right after a word boundary (`-`, `_`, `.`, space). Returns `None` if
+/// `query`'s characters don't all appear in `name`, in order.
+fn fuzzy_score(name: &str, query: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let name_chars: Vec<char> = name.chars().collect();
+    let query_chars: Vec<char> = query.chars().collect();
+
+    let mut score: i64 = 0;
+    let mut query_idx = 0;
+    let mut run_length = 0i64;
+    let mut last_match_idx: Option<usize> = None;
+
+    for (name_idx, &ch) in name_chars.iter().enumerate() {
+        if query_idx >= query_chars.len() {
+            break;
+        }
+        if ch.to_ascii_lowercase() != query_chars[query_idx].to_ascii_lowercase() {
+            continue;
+        }
+
+        let is_contiguous = last_match_idx == Some(name_idx.wrapping_sub(1));
+        run_length = if is_contiguous { run_length + 1 } else { 1 };
+        score += 10 + run_length * 5;
+
+        if name_idx == 0 {
+            score += 15; // prefix match
+        } else if matches!(name_chars[name_idx - 1], '-' | '_' | '.' | ' ') {
+            score += 8; // word-boundary match
+        }
+
+        last_match_idx = Some(name_idx);
+        query_idx += 1;
+    }
+
+    if query_idx < query_chars.len() {
+        return None; // not every query char was found, in order
+    }
+
+    // Shorter names that match the same query are slightly preferred.
+    score -= name_chars.len() as i64;
+    Some(score)
+}
+
+/// Scores `name` as a plain case-insensitive substring match: present or
+/// not, with a small bonus the earlier the match starts. `name` and `query`
+/// must already be lowercased by the caller.
+fn substring_score(name: &str, query: &str) -> Option<i64> {
+    let position = name.find(query)?;
+    Some(100 - position as i64)
+}
+
+fn passes_filters(options: &SearchOptions, path: &std::path::Path, metadata: &std::fs::Metadata) -> bool {
+    if !options.extensions.is_empty() {
+        let matches_extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|ext| {
+                options
+                    .extensions
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(ext))
+            })
+            .unwrap_or(false);
+        if !matches_extension {
+            return false;
+        }
+    }
+
+    let size = metadata.len();
+    if let Some(min_size) = options.min_size {
+        if size < min_size {
+            return false;
+        }
+    }
+    if let Some(max_size) = options.max_size {
+        if size > max_size {
+            return false;
+        }
+    }
+
+    if options.modified_after.is_some() || options.modified_before.is_some() {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        let Some(modified) = modified else {
+            return false;
+        };
+        if let Some(after) = options.modified_after {
+            if modified < after {
+                return false;
+            }
+        }
+        if let Some(before) = options.modified_before {
+            if modified > before {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+#[tauri::command]
+pub async fn search_files(
+    path: PathBuf,
+    search_id: u32,
+    options: SearchOptions,
+    state: tauri::State<'_, SearchManager>,
+    on_event: Channel<SearchEvent>,
+) -> Result<(), String> {
+    let _ = on_event.send(SearchEvent::Started {
+        query: options.query.clone(),
+        search_id,
+    });
+
+    let cancelled = state.register(search_id);
+    let query = options.query.to_lowercase();
+
+    // Globally optimal best-first ordering would require buffering the
+    // entire walk before sending anything, which is exactly the "wait for
+    // it all to finish" behavior this is meant to avoid. Instead we rank
+    // within a sliding window of MAX_RESULTS_PER_BATCH candidates and
+    // stream each window out as soon as it fills, so the caller sees
+    // results within a batch best-first while still getting them
+    // incrementally as the walk progresses.
+    let mut batch: Vec<Match> = Vec::new();
+    let mut total_matches: usize = 0;
+    let mut sent_results: usize = 0;
+
+    for entry in WalkDir::new(&path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if cancelled.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        let score = if options.fuzzy {
+            fuzzy_score(&name, &query)
+        } else {
+            substring_score(&name.to_lowercase(), &query)
+        };
+
+        let Some(score) = score else { continue };
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        if !passes_filters(&options, entry.path(), &metadata) {
+            continue;
+        }
+
+        total_matches += 1;
+
+        if sent_results + batch.len() >= MAX_TOTAL_RESULTS {
+            continue; // still counting total_matches, but the cap is full
+        }
+
+        batch.push(Match {
+            path: entry.path().to_path_buf(),
+            metadata,
+            score,
+        });
+
+        if batch.len() >= MAX_RESULTS_PER_BATCH {
+            sent_results += flush_batch(&mut batch, search_id, &on_event);
+        }
+    }
+
+    state.unregister(search_id);
+
+    let was_cancelled = cancelled.load(Ordering::SeqCst);
+    if was_cancelled {
+        let _ = on_event.send(SearchEvent::Finished {
+            search_id,
+            total_matches,
+            has_more: false,
+        });
+        return Ok(());
+    }
+
+    if !batch.is_empty() {
+        sent_results += flush_batch(&mut batch, search_id, &on_event);
+    }
+
+    let _ = on_event.send(SearchEvent::Finished {
+        search_id,
+        total_matches,
+        has_more: total_matches > sent_results,
+    });
+
+    Ok(())
+}
+
+/// Sorts one window of candidates best-first and emits them as
+/// `SearchEvent::Result`s, returning how many were sent.
+fn flush_batch(batch: &mut Vec<Match>, search_id: u32, on_event: &Channel<SearchEvent>) -> usize {
+    batch.sort_by(|a, b| b.score.cmp(&a.score));
+
+    for m in batch.iter() {
+        let absolute_path = absolute_clean_path(&m.path);
+        let name = m
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let _ = on_event.send(SearchEvent::Result {
+            search_id,
+            path: absolute_path,
+            name,
+            is_file: m.metadata.is_file(),
+            size: m.metadata.len(),
+            modified: m
+                .metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            score: m.score,
+        });
+    }
+
+    let sent = batch.len();
+    batch.clear();
+    sent
+}
+
+#[tauri::command]
+pub async fn cancel_search(state: tauri::State<'_, SearchManager>, search_id: u32) -> Result<(), String> {
+    state.cancel(search_id)
+}
@@ -0,0 +1,183 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::cas::fingerprint;
+use crate::jobs::{JobEvent, JobManager};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailResult {
+    cache_path: String,
+    data_uri: Option<String>,
+}
+
+/// Where generated thumbnails live on disk, keyed by the source file's
+/// (sampled) `cas_id` so the same file never gets re-encoded.
+pub struct ThumbnailCache {
+    dir: PathBuf,
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: PathBuf) -> Self {
+        let _ = std::fs::create_dir_all(&dir);
+        Self { dir }
+    }
+
+    fn path_for(&self, cas_id: &str) -> PathBuf {
+        self.dir.join(format!("{cas_id}.webp"))
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Decodes `path` (an image, or a video — in which case a representative
+/// frame is extracted via the bundled ffmpeg binding), downscales it to fit
+/// within `max_dim` on its longest side while preserving aspect ratio,
+/// encodes it to WebP, and writes it into `cache.dir` keyed by the file's
+/// `cas_id`. Returns the cache path immediately without re-encoding if a
+/// thumbnail already exists for that fingerprint.
+pub(crate) fn generate_and_cache(
+    path: &Path,
+    max_dim: u32,
+    cache: &ThumbnailCache,
+) -> Result<PathBuf, String> {
+    let cas_id = fingerprint(&path.to_path_buf(), false)?;
+    let cache_path = cache.path_for(&cas_id);
+
+    if cache_path.exists() {
+        return Ok(cache_path);
+    }
+
+    let image = decode_representative_frame(path)?;
+    let thumbnail = if image.width() > max_dim || image.height() > max_dim {
+        image.resize(max_dim, max_dim, image::imageops::FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let encoded = webp::Encoder::from_image(&thumbnail)
+        .map_err(|e| e.to_string())?
+        .encode(80.0);
+
+    std::fs::write(&cache_path, &*encoded).map_err(|e| e.to_string())?;
+    Ok(cache_path)
+}
+
+fn decode_representative_frame(path: &Path) -> Result<image::DynamicImage, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    if matches!(extension.as_str(), "mp4" | "mov" | "mkv" | "webm" | "avi") {
+        decode_video_frame(path)
+    } else {
+        image::open(path).map_err(|e| e.to_string())
+    }
+}
+
+/// Pulls a single frame (the first keyframe reached) out of a video file via
+/// `ffmpeg-next` and hands it back as a regular `DynamicImage` so it flows
+/// through the same resize/encode path as a still image.
+fn decode_video_frame(path: &Path) -> Result<image::DynamicImage, String> {
+    ffmpeg_next::init().map_err(|e| e.to_string())?;
+
+    let mut input = ffmpeg_next::format::input(&path).map_err(|e| e.to_string())?;
+    let stream = input
+        .streams()
+        .best(ffmpeg_next::media::Type::Video)
+        .ok_or("no video stream found")?;
+    let stream_index = stream.index();
+
+    let context = ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())
+        .map_err(|e| e.to_string())?;
+    let mut decoder = context.decoder().video().map_err(|e| e.to_string())?;
+
+    let mut scaler = ffmpeg_next::software::scaling::Context::get(
+        decoder.format(),
+        decoder.width(),
+        decoder.height(),
+        ffmpeg_next::format::Pixel::RGB24,
+        decoder.width(),
+        decoder.height(),
+        ffmpeg_next::software::scaling::Flags::BILINEAR,
+    )
+    .map_err(|e| e.to_string())?;
+
+    for (stream, packet) in input.packets() {
+        if stream.index() != stream_index {
+            continue;
+        }
+        decoder.send_packet(&packet).map_err(|e| e.to_string())?;
+
+        let mut frame = ffmpeg_next::frame::Video::empty();
+        if decoder.receive_frame(&mut frame).is_ok() {
+            let mut rgb_frame = ffmpeg_next::frame::Video::empty();
+            scaler.run(&frame, &mut rgb_frame).map_err(|e| e.to_string())?;
+
+            // ffmpeg pads each row of the plane to an alignment boundary, so
+            // for widths where `width * 3` isn't already aligned, the plane
+            // is longer than `width * height * 3` and can't be handed to
+            // `RgbImage::from_raw` as-is. Copy row by row using the actual
+            // stride to produce a tightly packed buffer.
+            let width = rgb_frame.width() as usize;
+            let height = rgb_frame.height() as usize;
+            let stride = rgb_frame.stride(0);
+            let data = rgb_frame.data(0);
+            let mut packed = Vec::with_capacity(width * height * 3);
+            for row in 0..height {
+                let start = row * stride;
+                packed.extend_from_slice(&data[start..start + width * 3]);
+            }
+
+            let buffer = image::RgbImage::from_raw(rgb_frame.width(), rgb_frame.height(), packed)
+                .ok_or("decoded frame had unexpected buffer size")?;
+            return Ok(image::DynamicImage::ImageRgb8(buffer));
+        }
+    }
+
+    Err("no decodable video frame found".to_string())
+}
+
+#[tauri::command]
+pub async fn generate_thumbnail(
+    path: PathBuf,
+    max_dim: u32,
+    as_data_uri: bool,
+    cache: tauri::State<'_, ThumbnailCache>,
+) -> Result<ThumbnailResult, String> {
+    let cache_path = generate_and_cache(&path, max_dim, &cache)?;
+
+    let data_uri = if as_data_uri {
+        let bytes = std::fs::read(&cache_path).map_err(|e| e.to_string())?;
+        Some(format!("data:image/webp;base64,{}", base64_encode(&bytes)))
+    } else {
+        None
+    };
+
+    Ok(ThumbnailResult {
+        cache_path: cache_path.to_string_lossy().to_string(),
+        data_uri,
+    })
+}
+
+/// Starts a background job (see `jobs::JobManager`) that thumbnails every
+/// path in `paths`, reporting progress as it goes.
+#[tauri::command]
+pub async fn start_thumbnail_job(
+    paths: Vec<PathBuf>,
+    max_dim: u32,
+    jobs: tauri::State<'_, JobManager>,
+    cache: tauri::State<'_, ThumbnailCache>,
+    on_event: tauri::ipc::Channel<JobEvent>,
+) -> Result<String, String> {
+    Ok(jobs.start_thumbnail_job(paths, max_dim, cache.dir().to_path_buf(), on_event))
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
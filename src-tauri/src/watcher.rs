@@ -0,0 +1,368 @@
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+
+use crate::clean_path;
+
+/// How long raw `notify` events are buffered before being coalesced and
+/// flushed. macOS FSEvents in particular can report a single folder create
+/// as several duplicate `Create` events, so we need a short window to
+/// collapse those into one `FsEvent` per actual change.
+const DEBOUNCE_TICK: Duration = Duration::from_millis(100);
+
+/// How long a lone rename "from" half is kept around waiting for its
+/// matching "to" half before we give up pairing them and just report it as
+/// removed.
+const RENAME_PAIR_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum FsEvent {
+    #[serde(rename_all = "camelCase")]
+    Created { path: String },
+    #[serde(rename_all = "camelCase")]
+    Removed { path: String },
+    #[serde(rename_all = "camelCase")]
+    Modified { path: String },
+    #[serde(rename_all = "camelCase")]
+    Renamed { from: String, to: String },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RawKind {
+    Created,
+    Removed,
+    Modified,
+}
+
+struct Pending {
+    kind: RawKind,
+    seen_at: Instant,
+}
+
+/// The "from" half of a rename that arrived as two separate `notify` events,
+/// waiting to be paired with its "to" half. On Linux this is keyed by the
+/// OS-provided tracker id — the same cookie the kernel uses to correlate
+/// inotify's `IN_MOVED_FROM`/`IN_MOVED_TO` pair for a single inode move. On
+/// macOS, where FSEvents gives no such cookie, it's queued FIFO instead and
+/// paired with the next `RenameMode::Any`/`Other` event whose path exists.
+struct PendingRenameFrom {
+    path: PathBuf,
+    seen_at: Instant,
+}
+
+/// Owns one `notify` watcher per active `watch_id` so switching directories
+/// can tear down the previous watch without disturbing others.
+pub struct WatchManager {
+    watches: Arc<Mutex<HashMap<String, WatchHandle>>>,
+}
+
+struct WatchHandle {
+    _watcher: RecommendedWatcher,
+    stop: Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl WatchManager {
+    pub fn new() -> Self {
+        Self {
+            watches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn watch_directory(
+        &self,
+        watch_id: String,
+        path: PathBuf,
+        on_event: Channel<FsEvent>,
+    ) -> Result<(), String> {
+        let (raw_tx, raw_rx) = channel::<Event>();
+
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| {
+                if let Ok(event) = res {
+                    let _ = raw_tx.send(event);
+                }
+            })
+            .map_err(|e| e.to_string())?;
+
+        watcher
+            .watch(&path, RecursiveMode::Recursive)
+            .map_err(|e| e.to_string())?;
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let stop_clone = stop.clone();
+
+        std::thread::spawn(move || debounce_loop(raw_rx, on_event, stop_clone));
+
+        // Replacing an already-registered watch_id drops the old watcher
+        // (disconnecting its debounce thread's receiver) and sets the old
+        // thread's `stop` flag, so that thread exits instead of running
+        // alongside the new one.
+        if let Some(previous) = self.watches.lock().unwrap().insert(
+            watch_id,
+            WatchHandle {
+                _watcher: watcher,
+                stop,
+            },
+        ) {
+            previous
+                .stop
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    pub fn unwatch_directory(&self, watch_id: &str) -> Result<(), String> {
+        let handle = self
+            .watches
+            .lock()
+            .unwrap()
+            .remove(watch_id)
+            .ok_or("watch not found")?;
+        handle.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Buffers raw `notify` events per path for one `DEBOUNCE_TICK`, collapsing
+/// duplicates before emitting to the frontend. Renames are detected only
+/// from the OS's own rename notifications (`ModifyKind::Name`), never
+/// guessed by pairing up coincidental create/remove events — on platforms
+/// without a pairing cookie (macOS) the two halves are still both
+/// `ModifyKind::Name` events, just correlated by arrival order and
+/// existence instead of a tracker id.
+fn debounce_loop(
+    raw_rx: std::sync::mpsc::Receiver<Event>,
+    on_event: Channel<FsEvent>,
+    stop: Arc<std::sync::atomic::AtomicBool>,
+) {
+    let mut pending: HashMap<PathBuf, Pending> = HashMap::new();
+    let mut rename_from: HashMap<usize, PendingRenameFrom> = HashMap::new();
+    let mut rename_any_from: VecDeque<PendingRenameFrom> = VecDeque::new();
+
+    loop {
+        if stop.load(std::sync::atomic::Ordering::SeqCst) {
+            return;
+        }
+
+        loop {
+            match raw_rx.recv_timeout(DEBOUNCE_TICK) {
+                Ok(event) => ingest(
+                    event,
+                    &mut pending,
+                    &mut rename_from,
+                    &mut rename_any_from,
+                    &on_event,
+                ),
+                Err(RecvTimeoutError::Timeout) => break,
+                // The sender was dropped, which means this watch was
+                // replaced or torn down; `stop` will already be set in that
+                // case, but bail out regardless rather than spin.
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            if stop.load(std::sync::atomic::Ordering::SeqCst) {
+                return;
+            }
+        }
+
+        expire_rename_from(&mut rename_from, &on_event);
+        expire_rename_any_from(&mut rename_any_from, &on_event);
+
+        if pending.is_empty() {
+            continue;
+        }
+
+        flush(&mut pending, &on_event);
+    }
+}
+
+/// Classifies one raw `notify` event, updating `pending`/`rename_from` and
+/// emitting directly for anything that's already unambiguous (a same-event
+/// rename, or a rename whose other half we already have on file).
+fn ingest(
+    event: Event,
+    pending: &mut HashMap<PathBuf, Pending>,
+    rename_from: &mut HashMap<usize, PendingRenameFrom>,
+    rename_any_from: &mut VecDeque<PendingRenameFrom>,
+    on_event: &Channel<FsEvent>,
+) {
+    if let EventKind::Modify(ModifyKind::Name(rename_mode)) = event.kind {
+        let tracker = event.attrs.tracker();
+
+        match rename_mode {
+            RenameMode::Both => {
+                if let [from, to] = &event.paths[..] {
+                    pending.remove(from);
+                    pending.remove(to);
+                    let _ = on_event.send(FsEvent::Renamed {
+                        from: clean_path(from.to_string_lossy().to_string()),
+                        to: clean_path(to.to_string_lossy().to_string()),
+                    });
+                }
+                return;
+            }
+            RenameMode::From => {
+                if let (Some(tracker), Some(path)) = (tracker, event.paths.first().cloned()) {
+                    rename_from.insert(
+                        tracker,
+                        PendingRenameFrom {
+                            path,
+                            seen_at: Instant::now(),
+                        },
+                    );
+                    return;
+                }
+            }
+            RenameMode::To => {
+                if let (Some(tracker), Some(to)) = (tracker, event.paths.first().cloned()) {
+                    if let Some(from) = rename_from.remove(&tracker) {
+                        pending.remove(&from.path);
+                        pending.remove(&to);
+                        let _ = on_event.send(FsEvent::Renamed {
+                            from: clean_path(from.path.to_string_lossy().to_string()),
+                            to: clean_path(to.to_string_lossy().to_string()),
+                        });
+                        return;
+                    }
+                    // No matching "from" half is on file for this tracker id —
+                    // treat this path as a plain create. (Without a tracker
+                    // id, the path is reported as modified further below.)
+                    insert_pending(pending, to, RawKind::Created);
+                    return;
+                }
+            }
+            RenameMode::Any | RenameMode::Other => {
+                // macOS FSEvents reports renames this way, without a
+                // tracker cookie to pair the two halves, so we fall back to
+                // a signal the event itself gives us: the "from" half's
+                // path no longer exists on disk, the "to" half's does.
+                // Pairing by arrival order within a short window is safe
+                // here because `notify` has already classified both halves
+                // as a rename (`ModifyKind::Name`) — unlike pairing
+                // unrelated Create/Remove events, we aren't guessing that
+                // two coincidental changes are related.
+                //
+                // FIFO order is the best available signal — by the time a
+                // "from" half reaches us its path is already gone, so there's
+                // no inode left to cross-check against the "to" half. Two
+                // renames landing in the same debounce window can therefore
+                // be cross-paired (A->B and C->D reported as A->D, C->B);
+                // this is a known limitation of the cookie-less platform
+                // case, not a bug in the pairing logic itself.
+                if let Some(path) = event.paths.first().cloned() {
+                    if path.exists() {
+                        if let Some(from) = rename_any_from.pop_front() {
+                            pending.remove(&from.path);
+                            pending.remove(&path);
+                            let _ = on_event.send(FsEvent::Renamed {
+                                from: clean_path(from.path.to_string_lossy().to_string()),
+                                to: clean_path(path.to_string_lossy().to_string()),
+                            });
+                        } else {
+                            insert_pending(pending, path, RawKind::Created);
+                        }
+                    } else {
+                        rename_any_from.push_back(PendingRenameFrom {
+                            path,
+                            seen_at: Instant::now(),
+                        });
+                    }
+                    return;
+                }
+            }
+        }
+        // Couldn't pair this rename half with anything — treat its paths as
+        // plain modifications rather than fabricating a rename.
+        for path in event.paths {
+            insert_pending(pending, path, RawKind::Modified);
+        }
+        return;
+    }
+
+    let kind = match event.kind {
+        EventKind::Create(_) => RawKind::Created,
+        EventKind::Remove(_) => RawKind::Removed,
+        EventKind::Modify(_) => RawKind::Modified,
+        _ => return,
+    };
+
+    for path in event.paths {
+        insert_pending(pending, path, kind);
+    }
+}
+
+fn insert_pending(pending: &mut HashMap<PathBuf, Pending>, path: PathBuf, kind: RawKind) {
+    // A duplicate event for a path already pending this tick is collapsed
+    // into the existing entry (macOS FSEvents quirk).
+    pending
+        .entry(path)
+        .and_modify(|existing| existing.seen_at = Instant::now())
+        .or_insert(Pending {
+            kind,
+            seen_at: Instant::now(),
+        });
+}
+
+/// Drops "from" halves of a rename that have waited longer than
+/// `RENAME_PAIR_TIMEOUT` for their "to" half, reporting each one as removed
+/// rather than silently forgetting it happened.
+fn expire_rename_from(
+    rename_from: &mut HashMap<usize, PendingRenameFrom>,
+    on_event: &Channel<FsEvent>,
+) {
+    let expired: Vec<usize> = rename_from
+        .iter()
+        .filter(|(_, entry)| entry.seen_at.elapsed() >= RENAME_PAIR_TIMEOUT)
+        .map(|(tracker, _)| *tracker)
+        .collect();
+
+    for tracker in expired {
+        if let Some(entry) = rename_from.remove(&tracker) {
+            let _ = on_event.send(FsEvent::Removed {
+                path: clean_path(entry.path.to_string_lossy().to_string()),
+            });
+        }
+    }
+}
+
+/// Same as `expire_rename_from`, for the cookie-less `RenameMode::Any`/
+/// `RenameMode::Other` "from" halves queued up by `ingest`.
+fn expire_rename_any_from(rename_any_from: &mut VecDeque<PendingRenameFrom>, on_event: &Channel<FsEvent>) {
+    while let Some(front) = rename_any_from.front() {
+        if front.seen_at.elapsed() < RENAME_PAIR_TIMEOUT {
+            break;
+        }
+        let entry = rename_any_from.pop_front().unwrap();
+        let _ = on_event.send(FsEvent::Removed {
+            path: clean_path(entry.path.to_string_lossy().to_string()),
+        });
+    }
+}
+
+fn flush(pending: &mut HashMap<PathBuf, Pending>, on_event: &Channel<FsEvent>) {
+    pending.retain(|path, entry| {
+        // Leave entries that haven't sat for a full DEBOUNCE_TICK yet so a
+        // burst of duplicate events for the same path still collapses into
+        // one `FsEvent`, even if it happens to straddle a tick boundary.
+        if entry.seen_at.elapsed() < DEBOUNCE_TICK {
+            return true;
+        }
+
+        let cleaned = clean_path(path.to_string_lossy().to_string());
+        let event = match entry.kind {
+            RawKind::Created => FsEvent::Created { path: cleaned },
+            RawKind::Removed => FsEvent::Removed { path: cleaned },
+            RawKind::Modified => FsEvent::Modified { path: cleaned },
+        };
+        let _ = on_event.send(event);
+        false
+    });
+}